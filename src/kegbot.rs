@@ -0,0 +1,188 @@
+//! Optional sync with a Kegbot-style REST server.
+//!
+//! Kegbot exposes kegs/taps and individual pours ("drinks") as JSON lists
+//! under an `objects` array. This module is feature-gated behind
+//! `kegbot-sync` so the core CLI carries no network dependency when the
+//! integration is unused.
+
+use std::collections::HashSet;
+use std::error::Error;
+
+use serde::Deserialize;
+
+use crate::{now_secs, Keg, KegTracker};
+
+/// A keg/tap as reported by the server's `/api/kegs` endpoint.
+#[derive(Deserialize)]
+struct ServerKeg {
+    id: String,
+    beer_type: String,
+    size_gallons: f32,
+    remaining_gallons: f32,
+    location: String,
+}
+
+/// A single pour ("drink") as reported by the server's `/api/drinks` endpoint.
+///
+/// `pour_id` echoes back the local [`Pour::id`] a drink was created from, so
+/// a later sync can tell it apart from every other drink recorded in the
+/// same second. Drinks the server knows about that didn't originate from
+/// this client (or predate this field) simply have no `pour_id`.
+#[derive(Deserialize)]
+struct ServerDrink {
+    #[serde(default)]
+    pour_id: Option<u64>,
+}
+
+/// Kegbot's list responses wrap results in an `objects` array.
+#[derive(Deserialize)]
+struct ServerList<T> {
+    objects: Vec<T>,
+}
+
+impl KegTracker {
+    /// Syncs local state with a Kegbot-style server at `base_url`.
+    ///
+    /// GETs the server's keg/tap list and drink log, reconciling kegs into
+    /// the local map by their stable `external_id`, then POSTs any
+    /// locally-recorded pours whose id doesn't already appear as a drink's
+    /// `pour_id` on the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request fails or a response can't be parsed.
+    pub fn sync(&mut self, base_url: &str, api_key: &str) -> Result<(), Box<dyn Error>> {
+        let client = reqwest::blocking::Client::new();
+
+        let kegs: ServerList<ServerKeg> = client
+            .get(format!("{base_url}/api/kegs"))
+            .bearer_auth(api_key)
+            .send()?
+            .error_for_status()?
+            .json()?;
+        for server_keg in kegs.objects {
+            self.reconcile_keg(server_keg);
+        }
+
+        let drinks: ServerList<ServerDrink> = client
+            .get(format!("{base_url}/api/drinks"))
+            .bearer_auth(api_key)
+            .send()?
+            .error_for_status()?
+            .json()?;
+        let known: HashSet<u64> = drinks
+            .objects
+            .into_iter()
+            .filter_map(|d| d.pour_id)
+            .collect();
+
+        for pour in self.pours.clone() {
+            if known.contains(&pour.id) {
+                continue;
+            }
+            let Some(external_id) = self.external_id_for(pour.keg_id) else {
+                continue;
+            };
+            client
+                .post(format!("{base_url}/api/drinks"))
+                .bearer_auth(api_key)
+                .json(&serde_json::json!({
+                    "keg_id": external_id,
+                    "ounces": pour.ounces,
+                    "ticks_time": pour.timestamp,
+                    "pour_id": pour.id,
+                }))
+                .send()?
+                .error_for_status()?;
+        }
+
+        Ok(())
+    }
+
+    /// Updates the local keg matching `server_keg.id`, or inserts a new one
+    /// if no local keg is synced to that external id yet.
+    fn reconcile_keg(&mut self, server_keg: ServerKeg) {
+        if let Some(keg) = self
+            .kegs
+            .values_mut()
+            .find(|k| k.external_id.as_deref() == Some(server_keg.id.as_str()))
+        {
+            keg.beer_type = server_keg.beer_type;
+            keg.size = server_keg.size_gallons;
+            keg.current_volume = server_keg.remaining_gallons;
+            keg.location = server_keg.location;
+            keg.last_updated = now_secs();
+        } else {
+            let id = self.next_id;
+            self.kegs.insert(
+                id,
+                Keg {
+                    id,
+                    beer_type: server_keg.beer_type,
+                    size: server_keg.size_gallons,
+                    current_volume: server_keg.remaining_gallons,
+                    location: server_keg.location,
+                    last_updated: now_secs(),
+                    external_id: Some(server_keg.id),
+                },
+            );
+            self.next_id += 1;
+        }
+    }
+
+    /// Looks up the external id a local keg is synced to, if any.
+    fn external_id_for(&self, id: u32) -> Option<String> {
+        self.kegs.get(&id).and_then(|k| k.external_id.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconcile_keg_inserts_unsynced_server_keg() {
+        let mut tracker = KegTracker::new();
+
+        tracker.reconcile_keg(ServerKeg {
+            id: "abc123".to_string(),
+            beer_type: "IPA".to_string(),
+            size_gallons: 5.0,
+            remaining_gallons: 3.0,
+            location: "Garage".to_string(),
+        });
+
+        assert_eq!(tracker.kegs.len(), 1);
+        let keg = tracker.kegs.values().next().unwrap();
+        assert_eq!(keg.beer_type, "IPA");
+        assert_eq!(keg.current_volume, 3.0);
+        assert_eq!(keg.external_id.as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn reconcile_keg_updates_existing_keg_with_matching_external_id() {
+        let mut tracker = KegTracker::new();
+        tracker.reconcile_keg(ServerKeg {
+            id: "abc123".to_string(),
+            beer_type: "IPA".to_string(),
+            size_gallons: 5.0,
+            remaining_gallons: 3.0,
+            location: "Garage".to_string(),
+        });
+
+        tracker.reconcile_keg(ServerKeg {
+            id: "abc123".to_string(),
+            beer_type: "IPA".to_string(),
+            size_gallons: 5.0,
+            remaining_gallons: 1.5,
+            location: "Basement".to_string(),
+        });
+
+        // The second sync must update the existing keg in place, not add a
+        // second one for the same external id.
+        assert_eq!(tracker.kegs.len(), 1);
+        let keg = tracker.kegs.values().next().unwrap();
+        assert_eq!(keg.current_volume, 1.5);
+        assert_eq!(keg.location, "Basement");
+    }
+}
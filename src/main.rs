@@ -4,10 +4,74 @@
 //! This module provides functionality to manage keg inventory, including
 //! adding new kegs, updating volumes, and listing current inventory.
 
-use std::io::{self, Write};
 use std::collections::HashMap;
+use std::error::Error;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::process::ExitCode;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use clap::{Parser, Subcommand};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "kegbot-sync")]
+mod kegbot;
+
+/// Default path for the CSV file kegs are persisted to between runs.
+const DEFAULT_DATA_FILE: &str = "kegs.csv";
+
+/// Default path for the CSV file pours are persisted to between runs.
+const DEFAULT_POURS_FILE: &str = "pours.csv";
+
+/// Seconds in a day, used to bucket pours into "today" for reporting.
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Fluid ounces in a US gallon, used to convert between the volume unit
+/// kegs are tracked in and the serving-size unit pours are estimated in.
+const OUNCES_PER_GALLON: f32 = 128.0;
+
+/// Default serving size, in ounces, used when estimating servings remaining.
+const DEFAULT_SERVING_SIZE_OZ: f32 = 12.0;
+
+/// Fill percentage at or above which a keg's fill column is shown in green.
+const FILL_PCT_GOOD: f32 = 50.0;
+
+/// Fill percentage at or above which a keg's fill column is shown in yellow
+/// rather than red.
+const FILL_PCT_WARN: f32 = 20.0;
+
+const ANSI_GREEN: &str = "\x1b[32m";
+const ANSI_YELLOW: &str = "\x1b[33m";
+const ANSI_RED: &str = "\x1b[31m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Picks the ANSI color for a keg's fill percentage: green when mostly
+/// full, yellow when getting low, red when nearly empty.
+fn fill_color(fill_pct: f32) -> &'static str {
+    if fill_pct >= FILL_PCT_GOOD {
+        ANSI_GREEN
+    } else if fill_pct >= FILL_PCT_WARN {
+        ANSI_YELLOW
+    } else {
+        ANSI_RED
+    }
+}
+
+/// Returns the current time as a Unix timestamp in seconds.
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
 /// Represents a single beer keg with its properties and current state.
 ///
 /// # Fields
@@ -18,6 +82,8 @@ use std::time::{SystemTime, UNIX_EPOCH};
 /// * `current_volume` - Current volume of beer in gallons
 /// * `location` - Physical location of the keg
 /// * `last_updated` - Unix timestamp of the last update
+/// * `external_id` - Stable ID on a remote server this keg is synced with, if any
+#[derive(Serialize, Deserialize)]
 struct Keg {
     id: u32,
     beer_type: String,
@@ -25,6 +91,37 @@ struct Keg {
     current_volume: f32,
     location: String,
     last_updated: u64,
+    #[serde(default)]
+    external_id: Option<String>,
+}
+
+/// A single recorded pour drawn from a keg.
+///
+/// # Fields
+///
+/// * `id` - Unique identifier for the pour, stable across syncs and reloads
+/// * `keg_id` - ID of the keg the pour was drawn from
+/// * `ounces` - Amount poured, in fluid ounces
+/// * `timestamp` - Unix timestamp of when the pour was recorded
+#[derive(Serialize, Deserialize, Clone)]
+struct Pour {
+    id: u64,
+    keg_id: u32,
+    ounces: f32,
+    timestamp: u64,
+}
+
+/// Aggregate pour statistics for a single keg.
+///
+/// # Fields
+///
+/// * `total_poured_oz` - Total ounces poured from the keg across all time
+/// * `pours_today` - Number of pours recorded since midnight (by Unix epoch day)
+/// * `servings_remaining` - Estimated servings left, based on a serving size
+struct KegStats {
+    total_poured_oz: f32,
+    pours_today: usize,
+    servings_remaining: f32,
 }
 
 /// Manages a collection of kegs and provides operations for keg tracking.
@@ -33,9 +130,13 @@ struct Keg {
 ///
 /// * `kegs` - HashMap storing all kegs with their IDs as keys
 /// * `next_id` - Counter for generating unique IDs for new kegs
+/// * `pours` - Consumption log of every pour recorded across all kegs
+/// * `next_pour_id` - Counter for generating unique IDs for new pours
 struct KegTracker {
     kegs: HashMap<u32, Keg>,
     next_id: u32,
+    pours: Vec<Pour>,
+    next_pour_id: u64,
 }
 
 impl KegTracker {
@@ -48,6 +149,8 @@ impl KegTracker {
         KegTracker {
             kegs: HashMap::new(),
             next_id: 1,
+            pours: Vec::new(),
+            next_pour_id: 1,
         }
     }
 
@@ -72,10 +175,8 @@ impl KegTracker {
             size,
             current_volume: size,
             location,
-            last_updated: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
+            last_updated: now_secs(),
+            external_id: None,
         };
         self.kegs.insert(self.next_id, keg);
         self.next_id += 1;
@@ -105,16 +206,70 @@ impl KegTracker {
                 return Err("Volume cannot exceed keg size");
             }
             keg.current_volume = volume;
-            keg.last_updated = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
+            keg.last_updated = now_secs();
             Ok(())
         } else {
             Err("Keg not found")
         }
     }
 
+    /// Records a pour of `ounces` from keg `id`, decrementing its current
+    /// volume and appending the pour to the consumption log.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The keg ID doesn't exist
+    /// - `ounces` isn't positive
+    /// - The pour would take the keg's volume negative
+    fn pour(&mut self, id: u32, ounces: f32) -> Result<(), &'static str> {
+        if ounces <= 0.0 {
+            return Err("Pour amount must be positive");
+        }
+        let keg = self.kegs.get_mut(&id).ok_or("Keg not found")?;
+        let gallons = ounces / OUNCES_PER_GALLON;
+        if gallons > keg.current_volume {
+            return Err("Pour exceeds remaining volume");
+        }
+        keg.current_volume -= gallons;
+        keg.last_updated = now_secs();
+        self.pours.push(Pour {
+            id: self.next_pour_id,
+            keg_id: id,
+            ounces,
+            timestamp: keg.last_updated,
+        });
+        self.next_pour_id += 1;
+        Ok(())
+    }
+
+    /// Computes pour aggregates for keg `id`: total ounces poured, number of
+    /// pours recorded today, and estimated servings remaining at
+    /// `serving_oz` per serving.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the keg doesn't exist.
+    fn keg_stats(&self, id: u32, serving_oz: f32) -> Result<KegStats, &'static str> {
+        let keg = self.kegs.get(&id).ok_or("Keg not found")?;
+        let today_start = now_secs() - (now_secs() % SECS_PER_DAY);
+
+        let mut total_poured_oz = 0.0;
+        let mut pours_today = 0;
+        for p in self.pours.iter().filter(|p| p.keg_id == id) {
+            total_poured_oz += p.ounces;
+            if p.timestamp >= today_start {
+                pours_today += 1;
+            }
+        }
+
+        Ok(KegStats {
+            total_poured_oz,
+            pours_today,
+            servings_remaining: (keg.current_volume * OUNCES_PER_GALLON) / serving_oz,
+        })
+    }
+
     /// Displays all kegs in a formatted table.
     ///
     /// # Output Format
@@ -124,103 +279,675 @@ impl KegTracker {
     /// - Beer Type
     /// - Size (gallons)
     /// - Current Volume (gallons)
+    /// - Fill % (colored green/yellow/red by how full the keg is)
     /// - Location
+    /// - Status ("LOW" when `threshold` is given and the keg is below it)
+    ///
+    /// Coloring is skipped automatically when stdout isn't a TTY, so piped
+    /// output stays clean.
     ///
     /// If no kegs exist, displays an appropriate message.
-    fn list_kegs(&self) {
+    fn list_kegs(&self, threshold: Option<f32>) {
         if self.kegs.is_empty() {
             println!("No kegs in the system.");
             return;
         }
+        let use_color = io::stdout().is_terminal();
         println!("\nCurrent Kegs:");
-        println!("ID\tBeer Type\tSize\tCurrent\tLocation");
+        println!("ID\tBeer Type\tSize\tCurrent\tFill %\tLocation\tStatus");
         println!("----------------------------------------");
         for keg in self.kegs.values() {
+            let fill_pct = if keg.size > 0.0 {
+                (keg.current_volume / keg.size) * 100.0
+            } else {
+                0.0
+            };
+            let fill_str = format!("{:.0}%", fill_pct);
+            let fill_display = if use_color {
+                format!("{}{}{}", fill_color(fill_pct), fill_str, ANSI_RESET)
+            } else {
+                fill_str
+            };
+            let status = match threshold {
+                Some(t) if fill_pct < t => "LOW",
+                _ => "",
+            };
             println!(
-                "{}\t{}\t{:.1}\t{:.1}\t{}",
-                keg.id, keg.beer_type, keg.size, keg.current_volume, keg.location
+                "{}\t{}\t{:.1}\t{:.1}\t{}\t{}\t{}",
+                keg.id,
+                keg.beer_type,
+                keg.size,
+                keg.current_volume,
+                fill_display,
+                keg.location,
+                status
             );
         }
     }
+
+    /// Prints every recorded pour in the consumption log, in the order they
+    /// were poured.
+    ///
+    /// If no pours exist, displays an appropriate message.
+    fn list_pours(&self) {
+        if self.pours.is_empty() {
+            println!("No pours recorded.");
+            return;
+        }
+        println!("\nPour Log:");
+        println!("Keg\tOunces\tTimestamp");
+        println!("----------------------------------------");
+        for pour in &self.pours {
+            println!("{}\t{:.1}\t{}", pour.keg_id, pour.ounces, pour.timestamp);
+        }
+    }
+
+    /// Writes every keg to `path` as CSV, one row per keg.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be created or a row can't be
+    /// written.
+    fn save_to_csv<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        let mut writer = csv::Writer::from_path(path)?;
+        for keg in self.kegs.values() {
+            writer.serialize(keg)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Loads kegs from `path`, replacing the tracker's current contents.
+    ///
+    /// `next_id` is restored to one past the highest id found in the file,
+    /// so subsequently added kegs don't collide with the loaded ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or a row fails to parse.
+    fn load_from_csv<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Box<dyn Error>> {
+        let mut reader = csv::Reader::from_path(path)?;
+        self.kegs.clear();
+        let mut max_id = 0;
+        for result in reader.deserialize() {
+            let keg: Keg = result?;
+            max_id = max_id.max(keg.id);
+            self.kegs.insert(keg.id, keg);
+        }
+        self.next_id = max_id + 1;
+        Ok(())
+    }
+
+    /// Writes every recorded pour to `path` as CSV, one row per pour.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be created or a row can't be
+    /// written.
+    fn save_pours_to_csv<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        let mut writer = csv::Writer::from_path(path)?;
+        for pour in &self.pours {
+            writer.serialize(pour)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Loads pours from `path`, replacing the tracker's current consumption
+    /// log.
+    ///
+    /// `next_pour_id` is restored to one past the highest id found in the
+    /// file, so subsequently recorded pours don't collide with the loaded
+    /// ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or a row fails to parse.
+    fn load_pours_from_csv<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Box<dyn Error>> {
+        let mut reader = csv::Reader::from_path(path)?;
+        self.pours.clear();
+        let mut max_id = 0;
+        for result in reader.deserialize() {
+            let pour: Pour = result?;
+            max_id = max_id.max(pour.id);
+            self.pours.push(pour);
+        }
+        self.next_pour_id = max_id + 1;
+        Ok(())
+    }
 }
 
-/// Main entry point of the application.
-///
-/// Provides a command-line interface for:
-/// 1. Adding new kegs
-/// 2. Updating keg volumes
-/// 3. Listing all kegs
-/// 4. Exiting the application
-///
-/// # User Interface
+/// Command-line interface for Keg Tracker, built with clap's derive API.
 ///
-/// The application runs in a loop, presenting a menu and processing user input
-/// until the user chooses to exit.
-fn main() {
-    let mut tracker = KegTracker::new();
-    let mut input = String::new();
+/// Each variant of [`Commands`] maps to one `KegTracker` operation. Numeric
+/// arguments are parsed (and validated) by clap itself, so an unparseable
+/// `--size` or `--volume` fails the whole invocation with a non-zero exit
+/// code instead of silently falling back to `0.0`.
+#[derive(Parser)]
+#[command(name = "keg", about = "Track beer kegs and their contents", version)]
+struct Cli {
+    /// CSV file kegs are loaded from and persisted to
+    #[arg(long, global = true, default_value = DEFAULT_DATA_FILE)]
+    data_file: std::path::PathBuf,
+
+    /// CSV file the pour log is loaded from and persisted to
+    #[arg(long, global = true, default_value = DEFAULT_POURS_FILE)]
+    pours_file: std::path::PathBuf,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Add a new keg to the tracker
+    Add {
+        /// Type of beer in the keg
+        #[arg(long)]
+        beer_type: String,
+        /// Total capacity of the keg in gallons
+        #[arg(long)]
+        size: f32,
+        /// Physical location of the keg
+        #[arg(long)]
+        location: String,
+    },
+    /// Update the current volume of an existing keg
+    Update {
+        /// ID of the keg to update
+        #[arg(long)]
+        id: u32,
+        /// New volume in gallons
+        #[arg(long)]
+        volume: f32,
+    },
+    /// List all kegs currently tracked
+    List {
+        /// Flag kegs with less than this percent full as "LOW"
+        #[arg(long)]
+        threshold: Option<f32>,
+    },
+    /// Record a pour drawn from an existing keg
+    Pour {
+        /// ID of the keg poured from
+        #[arg(long)]
+        id: u32,
+        /// Amount poured, in fluid ounces
+        #[arg(long)]
+        ounces: f32,
+    },
+    /// List every pour in the consumption log
+    Pours,
+    /// Show pour aggregates (total poured, pours today, servings remaining) for a keg
+    Stats {
+        /// ID of the keg to report on
+        #[arg(long)]
+        id: u32,
+        /// Serving size in ounces, used to estimate servings remaining
+        #[arg(long, default_value_t = DEFAULT_SERVING_SIZE_OZ)]
+        serving_size: f32,
+    },
+    /// Sync local state with a Kegbot-style REST server
+    #[cfg(feature = "kegbot-sync")]
+    Sync {
+        /// Base URL of the Kegbot server, e.g. https://kegbot.example.com
+        #[arg(long)]
+        base_url: String,
+        /// API key used to authenticate with the server
+        #[arg(long)]
+        api_key: String,
+    },
+    /// Fall back to the original interactive menu
+    Interactive,
+}
+
+/// Subcommand names the interactive shell accepts, also used to seed the
+/// completer.
+const SHELL_COMMANDS: &[&str] = &["add", "update", "list", "pour", "pours", "quit"];
+
+/// Path to the file the interactive shell's command history is persisted to.
+const HISTORY_FILE: &str = ".keg_tracker_history";
+
+/// Tab-completer for the interactive shell: the first word of a line
+/// completes against [`SHELL_COMMANDS`], and any later word completes
+/// against the ids of kegs currently known to the tracker.
+struct ShellCompleter {
+    keg_ids: Vec<u32>,
+}
+
+impl Completer for ShellCompleter {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let start = line[..pos]
+            .rfind(char::is_whitespace)
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let word = &line[start..pos];
+        let is_first_word = line[..start].trim().is_empty();
+
+        let candidates = if is_first_word {
+            SHELL_COMMANDS
+                .iter()
+                .filter(|c| c.starts_with(word))
+                .map(|c| c.to_string())
+                .collect()
+        } else {
+            self.keg_ids
+                .iter()
+                .map(|id| id.to_string())
+                .filter(|id| id.starts_with(word))
+                .collect()
+        };
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ShellCompleter {
+    type Hint = String;
+}
+
+impl Highlighter for ShellCompleter {}
+
+impl Validator for ShellCompleter {}
+
+impl Helper for ShellCompleter {}
+
+/// Runs a `rustyline`-backed interactive shell for people who prefer typed
+/// commands over flags: arrow-key line editing, persistent history recalled
+/// across runs, and tab-completion of subcommand names and keg ids.
+fn run_interactive(mut tracker: KegTracker, data_file: &Path, pours_file: &Path) {
+    let mut editor: Editor<ShellCompleter, DefaultHistory> =
+        Editor::new().expect("failed to initialize line editor");
+    editor.set_helper(Some(ShellCompleter {
+        keg_ids: tracker.kegs.keys().copied().collect(),
+    }));
+    if editor.load_history(HISTORY_FILE).is_err() {
+        // No existing history file yet; start fresh.
+    }
+
+    println!("Keg Tracker shell. Commands: {}", SHELL_COMMANDS.join(", "));
 
     loop {
-        println!("\nKeg Tracker Menu:");
-        println!("1. Add new keg");
-        println!("2. Update keg volume");
-        println!("3. List all kegs");
-        println!("4. Exit");
-        print!("Enter your choice: ");
-        io::stdout().flush().unwrap();
-
-        input.clear();
-        io::stdin().read_line(&mut input).unwrap();
-        let choice = input.trim();
-
-        match choice {
-            "1" => {
-                print!("Enter beer type: ");
-                io::stdout().flush().unwrap();
-                input.clear();
-                io::stdin().read_line(&mut input).unwrap();
-                let beer_type = input.trim().to_string();
-
-                print!("Enter keg size (gallons): ");
-                io::stdout().flush().unwrap();
-                input.clear();
-                io::stdin().read_line(&mut input).unwrap();
-                let size: f32 = input.trim().parse().unwrap_or(0.0);
-
-                print!("Enter location: ");
-                io::stdout().flush().unwrap();
-                input.clear();
-                io::stdin().read_line(&mut input).unwrap();
-                let location = input.trim().to_string();
+        if let Some(helper) = editor.helper_mut() {
+            helper.keg_ids = tracker.kegs.keys().copied().collect();
+        }
 
-                tracker.add_keg(beer_type, size, location);
+        let line = match editor.readline("keg> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Eof) | Err(ReadlineError::Interrupted) => break,
+            Err(e) => {
+                eprintln!("Error reading input: {}", e);
+                break;
             }
-            "2" => {
-                print!("Enter keg ID: ");
-                io::stdout().flush().unwrap();
-                input.clear();
-                io::stdin().read_line(&mut input).unwrap();
-                let id: u32 = input.trim().parse().unwrap_or(0);
+        };
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        editor.add_history_entry(trimmed).ok();
 
-                print!("Enter new volume (gallons): ");
-                io::stdout().flush().unwrap();
-                input.clear();
-                io::stdin().read_line(&mut input).unwrap();
-                let volume: f32 = input.trim().parse().unwrap_or(0.0);
+        let mut words = trimmed.split_whitespace();
+        match words.next().unwrap_or("") {
+            "add" => {
+                let beer_type = prompt_line(&mut editor, "Beer type: ");
+                let Ok(size) = prompt_line(&mut editor, "Size (gallons): ").parse::<f32>() else {
+                    println!("Invalid size; aborting add.");
+                    continue;
+                };
+                let location = prompt_line(&mut editor, "Location: ");
 
+                tracker.add_keg(beer_type, size, location);
+                if let Err(e) = tracker.save_to_csv(data_file) {
+                    println!("Warning: failed to save to {}: {}", data_file.display(), e);
+                }
+            }
+            "update" => {
+                let (id, volume) = match (words.next(), words.next()) {
+                    (Some(id), Some(volume)) => (id.parse().ok(), volume.parse().ok()),
+                    _ => {
+                        println!("Usage: update <id> <volume>");
+                        continue;
+                    }
+                };
+                let (Some(id), Some(volume)) = (id, volume) else {
+                    println!("Usage: update <id> <volume>");
+                    continue;
+                };
                 match tracker.update_keg(id, volume) {
-                    Ok(_) => println!("Keg updated successfully!"),
+                    Ok(()) => {
+                        println!("Keg updated successfully!");
+                        if let Err(e) = tracker.save_to_csv(data_file) {
+                            println!("Warning: failed to save to {}: {}", data_file.display(), e);
+                        }
+                    }
                     Err(e) => println!("Error: {}", e),
                 }
             }
-            "3" => {
-                tracker.list_kegs();
+            "list" => {
+                let threshold = words.next().and_then(|w| w.parse().ok());
+                tracker.list_kegs(threshold);
+            }
+            "pour" => {
+                let (id, ounces) = match (words.next(), words.next()) {
+                    (Some(id), Some(ounces)) => (id.parse().ok(), ounces.parse().ok()),
+                    _ => {
+                        println!("Usage: pour <id> <ounces>");
+                        continue;
+                    }
+                };
+                let (Some(id), Some(ounces)) = (id, ounces) else {
+                    println!("Usage: pour <id> <ounces>");
+                    continue;
+                };
+                match tracker.pour(id, ounces) {
+                    Ok(()) => {
+                        println!("Pour recorded!");
+                        if let Err(e) = tracker.save_to_csv(data_file) {
+                            println!("Warning: failed to save to {}: {}", data_file.display(), e);
+                        }
+                        if let Err(e) = tracker.save_pours_to_csv(pours_file) {
+                            println!("Warning: failed to save to {}: {}", pours_file.display(), e);
+                        }
+                    }
+                    Err(e) => println!("Error: {}", e),
+                }
             }
-            "4" => {
+            "pours" => tracker.list_pours(),
+            "quit" | "exit" => {
                 println!("Exiting...");
                 break;
             }
-            _ => println!("Invalid choice. Please try again."),
+            other => println!(
+                "Unknown command '{}'. Try: {}",
+                other,
+                SHELL_COMMANDS.join(", ")
+            ),
+        }
+    }
+
+    if let Err(e) = editor.save_history(HISTORY_FILE) {
+        println!("Warning: failed to save history to {}: {}", HISTORY_FILE, e);
+    }
+}
+
+/// Reads one line from `editor` with `prompt`, adding it to history.
+fn prompt_line(editor: &mut Editor<ShellCompleter, DefaultHistory>, prompt: &str) -> String {
+    let line = editor.readline(prompt).unwrap_or_default();
+    editor.add_history_entry(line.trim()).ok();
+    line.trim().to_string()
+}
+
+/// Main entry point of the application.
+///
+/// Parses a subcommand with clap and dispatches to the corresponding
+/// `KegTracker` operation, or falls back to the interactive menu. The keg
+/// data file is loaded first (if present) and saved again after any
+/// mutating operation, so kegs survive across runs. Returns a non-zero exit
+/// code when an operation fails.
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut tracker = KegTracker::new();
+
+    if cli.data_file.exists() {
+        if let Err(e) = tracker.load_from_csv(&cli.data_file) {
+            eprintln!("Error loading {}: {}", cli.data_file.display(), e);
+            return ExitCode::FAILURE;
+        }
+    }
+    if cli.pours_file.exists() {
+        if let Err(e) = tracker.load_pours_from_csv(&cli.pours_file) {
+            eprintln!("Error loading {}: {}", cli.pours_file.display(), e);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    match cli.command {
+        Commands::Add {
+            beer_type,
+            size,
+            location,
+        } => {
+            tracker.add_keg(beer_type, size, location);
+            if let Err(e) = tracker.save_to_csv(&cli.data_file) {
+                eprintln!("Error saving {}: {}", cli.data_file.display(), e);
+                return ExitCode::FAILURE;
+            }
+            ExitCode::SUCCESS
+        }
+        Commands::Update { id, volume } => match tracker.update_keg(id, volume) {
+            Ok(()) => {
+                println!("Keg updated successfully!");
+                if let Err(e) = tracker.save_to_csv(&cli.data_file) {
+                    eprintln!("Error saving {}: {}", cli.data_file.display(), e);
+                    return ExitCode::FAILURE;
+                }
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                ExitCode::FAILURE
+            }
+        },
+        Commands::List { threshold } => {
+            tracker.list_kegs(threshold);
+            ExitCode::SUCCESS
+        }
+        Commands::Pour { id, ounces } => match tracker.pour(id, ounces) {
+            Ok(()) => {
+                println!("Pour recorded!");
+                if let Err(e) = tracker.save_to_csv(&cli.data_file) {
+                    eprintln!("Error saving {}: {}", cli.data_file.display(), e);
+                    return ExitCode::FAILURE;
+                }
+                if let Err(e) = tracker.save_pours_to_csv(&cli.pours_file) {
+                    eprintln!("Error saving {}: {}", cli.pours_file.display(), e);
+                    return ExitCode::FAILURE;
+                }
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                ExitCode::FAILURE
+            }
+        },
+        Commands::Pours => {
+            tracker.list_pours();
+            ExitCode::SUCCESS
+        }
+        Commands::Stats { id, serving_size } => match tracker.keg_stats(id, serving_size) {
+            Ok(stats) => {
+                println!("Total poured: {:.1} oz", stats.total_poured_oz);
+                println!("Pours today: {}", stats.pours_today);
+                println!(
+                    "Estimated servings remaining: {:.1}",
+                    stats.servings_remaining
+                );
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                ExitCode::FAILURE
+            }
+        },
+        #[cfg(feature = "kegbot-sync")]
+        Commands::Sync { base_url, api_key } => match tracker.sync(&base_url, &api_key) {
+            Ok(()) => {
+                println!("Synced with {base_url}");
+                if let Err(e) = tracker.save_to_csv(&cli.data_file) {
+                    eprintln!("Error saving {}: {}", cli.data_file.display(), e);
+                    return ExitCode::FAILURE;
+                }
+                if let Err(e) = tracker.save_pours_to_csv(&cli.pours_file) {
+                    eprintln!("Error saving {}: {}", cli.pours_file.display(), e);
+                    return ExitCode::FAILURE;
+                }
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("Error syncing: {}", e);
+                ExitCode::FAILURE
+            }
+        },
+        Commands::Interactive => {
+            run_interactive(tracker, &cli.data_file, &cli.pours_file);
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a path under the OS temp dir that's unique to this test run,
+    /// so parallel tests don't clobber each other's CSV files.
+    fn temp_csv_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "keg_tracker_test_{}_{}.csv",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn csv_round_trip_restores_kegs_and_next_id() {
+        let path = temp_csv_path("round_trip");
+        let mut tracker = KegTracker::new();
+        tracker.add_keg("IPA".to_string(), 5.0, "Garage".to_string());
+        tracker.add_keg("Stout".to_string(), 5.0, "Garage".to_string());
+        tracker.update_keg(2, 3.0).unwrap();
+
+        tracker.save_to_csv(&path).unwrap();
+
+        let mut loaded = KegTracker::new();
+        loaded.load_from_csv(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.kegs.len(), 2);
+        assert_eq!(loaded.kegs[&1].beer_type, "IPA");
+        assert_eq!(loaded.kegs[&2].current_volume, 3.0);
+        // next_id must be one past the highest loaded id, or a
+        // subsequently added keg would collide with an existing one.
+        assert_eq!(loaded.next_id, 3);
+    }
+
+    #[test]
+    fn pour_decrements_volume_and_logs_the_pour() {
+        let mut tracker = KegTracker::new();
+        tracker.add_keg("IPA".to_string(), 5.0, "Garage".to_string());
+
+        tracker.pour(1, 128.0).unwrap(); // one gallon
+
+        assert_eq!(tracker.kegs[&1].current_volume, 4.0);
+        assert_eq!(tracker.pours.len(), 1);
+        assert_eq!(tracker.pours[0].ounces, 128.0);
+    }
+
+    #[test]
+    fn pour_rejects_zero_or_negative_ounces() {
+        let mut tracker = KegTracker::new();
+        tracker.add_keg("IPA".to_string(), 5.0, "Garage".to_string());
+
+        assert!(tracker.pour(1, 0.0).is_err());
+        assert!(tracker.pour(1, -12.0).is_err());
+        // Rejected pours must not touch the keg's volume or the log.
+        assert_eq!(tracker.kegs[&1].current_volume, 5.0);
+        assert!(tracker.pours.is_empty());
+    }
+
+    #[test]
+    fn pour_rejects_amount_exceeding_remaining_volume() {
+        let mut tracker = KegTracker::new();
+        tracker.add_keg("IPA".to_string(), 5.0, "Garage".to_string());
+
+        let err = tracker.pour(1, 5.0 * OUNCES_PER_GALLON + 1.0).unwrap_err();
+        assert_eq!(err, "Pour exceeds remaining volume");
+        assert_eq!(tracker.kegs[&1].current_volume, 5.0);
+    }
+
+    #[test]
+    fn pour_rejects_unknown_keg() {
+        let mut tracker = KegTracker::new();
+        assert!(tracker.pour(1, 12.0).is_err());
+    }
+
+    #[test]
+    fn keg_stats_buckets_pours_by_day() {
+        let mut tracker = KegTracker::new();
+        tracker.add_keg("IPA".to_string(), 5.0, "Garage".to_string());
+        tracker.pour(1, 12.0).unwrap(); // recorded "now" via pour()
+        tracker.pours.push(Pour {
+            id: 2,
+            keg_id: 1,
+            ounces: 12.0,
+            timestamp: now_secs() - SECS_PER_DAY - 1, // yesterday, or earlier
+        });
+
+        let stats = tracker.keg_stats(1, DEFAULT_SERVING_SIZE_OZ).unwrap();
+
+        assert_eq!(stats.total_poured_oz, 24.0);
+        assert_eq!(stats.pours_today, 1);
+    }
+
+    #[test]
+    fn fill_color_is_green_at_and_above_good_threshold() {
+        assert_eq!(fill_color(FILL_PCT_GOOD), ANSI_GREEN);
+        assert_eq!(fill_color(100.0), ANSI_GREEN);
+    }
+
+    #[test]
+    fn fill_color_is_yellow_between_warn_and_good_thresholds() {
+        assert_eq!(fill_color(FILL_PCT_GOOD - 0.1), ANSI_YELLOW);
+        assert_eq!(fill_color(FILL_PCT_WARN), ANSI_YELLOW);
+    }
+
+    #[test]
+    fn fill_color_is_red_below_warn_threshold() {
+        assert_eq!(fill_color(FILL_PCT_WARN - 0.1), ANSI_RED);
+        assert_eq!(fill_color(0.0), ANSI_RED);
+    }
+
+    #[test]
+    fn cli_parses_add_command() {
+        let cli = Cli::try_parse_from([
+            "keg",
+            "add",
+            "--beer-type",
+            "IPA",
+            "--size",
+            "5.0",
+            "--location",
+            "Garage",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Add {
+                beer_type,
+                size,
+                location,
+            } => {
+                assert_eq!(beer_type, "IPA");
+                assert_eq!(size, 5.0);
+                assert_eq!(location, "Garage");
+            }
+            _ => panic!("expected Commands::Add"),
         }
     }
+
+    #[test]
+    fn cli_rejects_add_command_missing_required_arg() {
+        // `--size` is required; omitting it should fail parsing rather than
+        // silently defaulting.
+        assert!(
+            Cli::try_parse_from(["keg", "add", "--beer-type", "IPA", "--location", "Garage"])
+                .is_err()
+        );
+    }
 }